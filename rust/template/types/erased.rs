@@ -0,0 +1,113 @@
+//! Object-safe (type-erased) serialization for `DDValue`, built on top of
+//! `erased_serde`, for tooling (e.g. a generic `dump <relation>` command)
+//! that needs to serialize relation values behind a trait object.
+
+use ::differential_datalog::ddval::DDValue;
+use ::erased_serde::{Deserializer as ErasedDeserializer, Error as ErasedError, Serializer as ErasedSerializer};
+use std::collections::HashMap;
+
+/// Object-safe counterpart of `Val`: every `T: Val` can be serialized through
+/// a `&mut dyn ErasedSerializer` without the caller knowing `T`.
+pub trait ErasedVal {
+    fn erased_serialize(&self, serializer: &mut dyn ErasedSerializer) -> Result<(), ErasedError>;
+}
+
+impl<T> ErasedVal for T
+where
+    T: crate::Val,
+{
+    fn erased_serialize(&self, serializer: &mut dyn ErasedSerializer) -> Result<(), ErasedError> {
+        ::erased_serde::Serialize::erased_serialize(self, serializer)
+    }
+}
+
+/// Deserializes a `DDValue` of one relation's concrete type out of an erased
+/// deserializer, then wraps it back up as a `DDValue`.
+fn erased_deserialize_val<T>(deserializer: &mut dyn ErasedDeserializer) -> Result<DDValue, ErasedError>
+where
+    T: crate::Val + Into<DDValue>,
+{
+    let val: T = ::erased_serde::deserialize(deserializer)?;
+    Ok(val.into())
+}
+
+/// A deserializer closure for one relation, keyed by the relation's name.
+pub type ErasedDeserializeFn = fn(&mut dyn ErasedDeserializer) -> Result<DDValue, ErasedError>;
+
+/// Maps a relation name to the function that knows how to deserialize a
+/// `DDValue` of that relation's concrete type out of an erased deserializer,
+/// so callers can go from a relation name straight to a `DDValue` without
+/// knowing the relation's concrete record type. A new registry is empty;
+/// entries are added only through `register_val`, and nothing in this crate
+/// registers any relation.
+#[derive(Default)]
+pub struct ErasedRegistry {
+    deserializers: HashMap<&'static str, ErasedDeserializeFn>,
+}
+
+impl ErasedRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the deserializer for `relation`, whose concrete value type
+    /// is `T`. Typically called once per relation at startup, e.g.
+    /// `registry.register_val::<relations::SomeRelation>("SomeRelation")`.
+    pub fn register_val<T>(&mut self, relation: &'static str)
+    where
+        T: crate::Val + Into<DDValue>,
+    {
+        self.deserializers.insert(relation, erased_deserialize_val::<T>);
+    }
+
+    pub fn deserialize(
+        &self,
+        relation: &str,
+        deserializer: &mut dyn ErasedDeserializer,
+    ) -> Result<DDValue, ErasedError> {
+        match self.deserializers.get(relation) {
+            Some(f) => f(deserializer),
+            None => Err(<::erased_serde::Error as ::serde::de::Error>::custom(format!(
+                "no erased deserializer registered for relation '{}'",
+                relation
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // `register_val`/`erased_deserialize_val` need a concrete `T: Into<DDValue>`,
+    // and `DDValue` lives in the (here absent) `differential_datalog` crate, so
+    // only the relation-name-agnostic parts of this module can be exercised:
+    // `ErasedVal::erased_serialize` against a plain `Val` type, and the
+    // not-found branch of `ErasedRegistry::deserialize`.
+
+    #[test]
+    fn erased_serialize_matches_plain_serialize() {
+        let val: u32 = 42;
+
+        let expected = serde_json::to_string(&val).unwrap();
+
+        let mut buf = Vec::new();
+        let mut json_ser = serde_json::Serializer::new(&mut buf);
+        let mut erased_ser = <dyn ErasedSerializer>::erase(&mut json_ser);
+        val.erased_serialize(&mut erased_ser).unwrap();
+
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn deserialize_unknown_relation_errors() {
+        let registry = ErasedRegistry::new();
+        let mut json_de = serde_json::Deserializer::from_str("0");
+        let mut erased_de = <dyn ErasedDeserializer>::erase(&mut json_de);
+
+        let err = registry
+            .deserialize("NoSuchRelation", &mut erased_de)
+            .unwrap_err();
+        assert!(err.to_string().contains("NoSuchRelation"));
+    }
+}
@@ -0,0 +1,56 @@
+//! `FromFlatBuffer`/`ToFlatBuffer` trait declarations and the lazy accessor
+//! layer built on top of them.
+//!
+//! Concrete conversions for DDlog types are generated from the `flatc`
+//! schema output in `flatbuf_generated` and are not part of this template.
+
+use ::differential_datalog::record::Record;
+
+/// Converts a value out of its generated FlatBuffers table representation,
+/// fully materializing it as a Rust value.
+pub trait FromFlatBuffer<T>: Sized {
+    fn from_flatbuf(fb: T) -> ::std::result::Result<Self, String>;
+}
+
+/// Converts a value into its generated FlatBuffers table representation.
+pub trait ToFlatBuffer<'b> {
+    type Target;
+    fn to_flatbuf(&self, fbb: &mut ::flatbuffers::FlatBufferBuilder<'b>) -> Self::Target;
+}
+
+/// Like `ToFlatBuffer`, but for a value serialized as one element of a
+/// FlatBuffers vector; `Target` is the type of that single element.
+pub trait ToFlatBufferVectorElement<'b> {
+    type Target;
+    fn to_flatbuf_vector_element(&self, fbb: &mut ::flatbuffers::FlatBufferBuilder<'b>) -> Self::Target;
+}
+
+/// A lazy, zero-copy view over a FlatBuffers table: holds on to the table
+/// offset and reads fields on demand from the underlying buffer, instead of
+/// fully materializing via `FromFlatBuffer` up front. Fields are read by
+/// name through `field`. This module declares the trait only; it holds no
+/// implementations, and none are generated for DDlog types.
+pub trait LazyView<'b> {
+    /// The generated FlatBuffers table type this view reads from.
+    type Table: 'b;
+
+    /// Wraps a table offset without reading or validating any of its
+    /// fields.
+    fn from_table(table: Self::Table) -> Self;
+
+    /// Looks up a field's record representation by name, reading only that
+    /// field out of the underlying buffer.
+    fn field(&self, name: &str) -> ::std::result::Result<Record, String>;
+
+    /// Fully materializes the value this view points at.
+    fn materialize<T: FromFlatBuffer<Self::Table>>(&self) -> ::std::result::Result<T, String>
+    where
+        Self: Sized + Clone,
+        Self::Table: Clone,
+    {
+        T::from_flatbuf(self.clone().into_table())
+    }
+
+    /// Recovers the underlying table, e.g. to hand to `FromFlatBuffer`.
+    fn into_table(self) -> Self::Table;
+}
@@ -0,0 +1,101 @@
+//! Runtime-selectable binary serialization formats, as a cheaper alternative
+//! to JSON records for high-throughput input/output deltas.
+
+use crate::Val;
+use std::fmt;
+
+/// A serde data format that can be selected at runtime.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Format {
+    Json,
+    Cbor,
+    MessagePack,
+    Bincode,
+}
+
+#[derive(Debug)]
+pub struct FormatError(String);
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(e: serde_json::Error) -> Self {
+        FormatError(e.to_string())
+    }
+}
+
+impl From<serde_cbor::Error> for FormatError {
+    fn from(e: serde_cbor::Error) -> Self {
+        FormatError(e.to_string())
+    }
+}
+
+impl From<rmp_serde::encode::Error> for FormatError {
+    fn from(e: rmp_serde::encode::Error) -> Self {
+        FormatError(e.to_string())
+    }
+}
+
+impl From<rmp_serde::decode::Error> for FormatError {
+    fn from(e: rmp_serde::decode::Error) -> Self {
+        FormatError(e.to_string())
+    }
+}
+
+impl From<bincode::Error> for FormatError {
+    fn from(e: bincode::Error) -> Self {
+        FormatError(e.to_string())
+    }
+}
+
+/// Serializes `val` using the given wire format.
+pub fn serialize_val<T: Val>(val: &T, format: Format) -> Result<Vec<u8>, FormatError> {
+    Ok(match format {
+        Format::Json => serde_json::to_vec(val)?,
+        Format::Cbor => serde_cbor::to_vec(val)?,
+        Format::MessagePack => rmp_serde::to_vec(val)?,
+        Format::Bincode => bincode::serialize(val)?,
+    })
+}
+
+/// Deserializes a `T` previously produced by `serialize_val` with the same
+/// format.
+pub fn deserialize_val<T: Val>(bytes: &[u8], format: Format) -> Result<T, FormatError> {
+    Ok(match format {
+        Format::Json => serde_json::from_slice(bytes)?,
+        Format::Cbor => serde_cbor::from_slice(bytes)?,
+        Format::MessagePack => rmp_serde::from_slice(bytes)?,
+        Format::Bincode => bincode::deserialize(bytes)?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn roundtrip<T: Val + std::fmt::Debug>(val: T, format: Format) {
+        let bytes = serialize_val(&val, format).unwrap();
+        let decoded: T = deserialize_val(&bytes, format).unwrap();
+        assert_eq!(decoded, val);
+    }
+
+    #[test]
+    fn roundtrips_every_format() {
+        for &format in &[
+            Format::Json,
+            Format::Cbor,
+            Format::MessagePack,
+            Format::Bincode,
+        ] {
+            roundtrip(42u32, format);
+            roundtrip(vec![1u32, 2, 3], format);
+            roundtrip(String::from("hello"), format);
+        }
+    }
+}
@@ -59,7 +59,10 @@ mod flatbuf_generated;
 pub mod flatbuf;
 
 pub mod ddval_convert;
+pub mod erased;
+pub mod format;
 pub mod int;
+pub mod serde_helpers;
 pub mod uint;
 
 pub trait Val:
@@ -101,6 +104,10 @@ pub fn string_append(mut s1: String, s2: &String) -> String {
     s1
 }
 
+// A `ddlog_std::Map` field or type can be routed through custom
+// `serialize_with`/`deserialize_with` functions (see `serde_helpers`) to keep
+// a fixed wire format; this macro generates one such pair for the common case
+// of a map serialized as a flat array of values, with keys recovered on load.
 #[macro_export]
 macro_rules! deserialize_map_from_array {
     ( $modname:ident, $ktype:ty, $vtype:ty, $kfunc:path ) => {
@@ -133,6 +140,173 @@ macro_rules! deserialize_map_from_array {
     };
 }
 
+// Like `deserialize_map_from_array!`, but serializes the map as a genuine
+// JSON-style object (`{ "key": value, ... }`) keyed by a string derived from
+// each value, for formats where object-shaped maps round-trip more
+// naturally than flat arrays. Falls back to the array encoding whenever
+// `serializer.is_human_readable()` is false: that covers formats that aren't
+// self-describing, such as `bincode`, and also binary self-describing ones
+// such as CBOR and MessagePack.
+// `$maptype` is the full map type (e.g. `crate::ddlog_std::Map<$ktype,
+// $vtype>`), taken as its own parameter rather than hardcoded, so the macro
+// can be exercised in tests against a stand-in map type without touching the
+// real `ddlog_std` module.
+#[macro_export]
+macro_rules! deserialize_map_from_object {
+    ( $modname:ident, $maptype:ty, $ktype:ty, $vtype:ty, $kfunc:path, $tostr:path, $fromstr:path ) => {
+        mod $modname {
+            use super::*;
+            use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+            use serde::ser::{SerializeMap, Serializer};
+            use std::collections::BTreeMap;
+            use std::fmt;
+
+            pub fn serialize<S>(map: &$maptype, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                if serializer.is_human_readable() {
+                    let mut m = serializer.serialize_map(Some(map.x.len()))?;
+                    for v in map.x.values() {
+                        m.serialize_entry(&$tostr($kfunc(v)), v)?;
+                    }
+                    m.end()
+                } else {
+                    serializer.collect_seq(map.x.values())
+                }
+            }
+
+            struct MapOrSeqVisitor;
+
+            impl<'de> Visitor<'de> for MapOrSeqVisitor {
+                type Value = $maptype;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "a map object or a sequence of values")
+                }
+
+                fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+                where
+                    A: MapAccess<'de>,
+                {
+                    let mut result = BTreeMap::new();
+                    while let Some((key, value)) = access.next_entry::<String, $vtype>()? {
+                        let key = $fromstr(&key).map_err(serde::de::Error::custom)?;
+                        result.insert(key, value);
+                    }
+                    Ok(result.into_iter().collect())
+                }
+
+                fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    let mut result = BTreeMap::new();
+                    while let Some(value) = access.next_element::<$vtype>()? {
+                        result.insert($kfunc(&value), value);
+                    }
+                    Ok(result.into_iter().collect())
+                }
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<$maptype, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                // Mirror `serialize`'s `is_human_readable` check rather than
+                // probing the shape with `deserialize_any`: non-self-describing
+                // formats such as `bincode` don't implement `deserialize_any`
+                // at all (there's no way for them to tell map from seq without
+                // a type hint), so they must be told which shape to expect.
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_map(MapOrSeqVisitor)
+                } else {
+                    deserializer.deserialize_seq(MapOrSeqVisitor)
+                }
+            }
+        }
+    };
+}
+
+// Minimal stand-in for `ddlog_std::Map`, used only to exercise
+// `deserialize_map_from_object!` below. Deliberately NOT named `ddlog_std`:
+// the real module of that name is spliced in below the "test-compile
+// template" marker at the bottom of this file when the full generated
+// program is test-compiled, so reusing the name here would collide with it.
+#[cfg(test)]
+mod test_ddlog_std {
+    use std::collections::BTreeMap;
+    use std::iter::FromIterator;
+
+    #[derive(Default, Eq, PartialEq, Debug)]
+    pub struct Map<K: Ord, V> {
+        pub x: BTreeMap<K, V>,
+    }
+
+    impl<K: Ord, V> FromIterator<(K, V)> for Map<K, V> {
+        fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+            Map {
+                x: BTreeMap::from_iter(iter),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_ddlog_std::Map;
+
+    fn key_of(v: &u32) -> u32 {
+        *v
+    }
+
+    fn key_to_string(k: u32) -> String {
+        k.to_string()
+    }
+
+    fn key_from_string(s: &str) -> Result<u32, std::num::ParseIntError> {
+        s.parse()
+    }
+
+    deserialize_map_from_object!(
+        object_map,
+        Map<u32, u32>,
+        u32,
+        u32,
+        key_of,
+        key_to_string,
+        key_from_string
+    );
+
+    fn sample_map() -> Map<u32, u32> {
+        vec![(1, 10), (2, 20), (3, 30)].into_iter().collect()
+    }
+
+    #[test]
+    fn roundtrips_as_object_over_human_readable_formats() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Wrapper(#[serde(with = "object_map")] Map<u32, u32>);
+
+        let map = sample_map();
+        let json = serde_json::to_string(&Wrapper(map)).unwrap();
+        assert!(json.contains("\"1\":10"), "expected an object, got: {}", json);
+        let Wrapper(decoded) = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, sample_map());
+    }
+
+    #[test]
+    fn roundtrips_as_array_over_non_self_describing_formats() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Wrapper(#[serde(with = "object_map")] Map<u32, u32>);
+
+        let map = sample_map();
+        let bytes = bincode::serialize(&Wrapper(map)).unwrap();
+        assert_eq!(bincode::serialize(&vec![10u32, 20, 30]).unwrap(), bytes);
+        let Wrapper(decoded) = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded, sample_map());
+    }
+}
+
 /*- !!!!!!!!!!!!!!!!!!!! -*/
 // Don't edit this line
 // Code below this point is needed to test-compile template
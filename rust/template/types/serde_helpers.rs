@@ -0,0 +1,145 @@
+//! Built-in `serialize_with`/`deserialize_with` helpers for DDlog types.
+//!
+//! A DDlog type or record field can be annotated to route through a custom
+//! `serialize_with`/`deserialize_with` function path to keep a fixed wire
+//! format, e.g.:
+//!
+//! ```ignore
+//! #[serde(serialize_with = "crate::serde_helpers::hex::serialize",
+//!         deserialize_with = "crate::serde_helpers::hex::deserialize")]
+//! bytes: Vec<u8>
+//! ```
+//!
+//! A custom helper must provide two free functions with these signatures:
+//!
+//! ```ignore
+//! pub fn serialize<S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+//! where
+//!     S: ::serde::Serializer;
+//!
+//! pub fn deserialize<'de, D>(deserializer: D) -> Result<T, D::Error>
+//! where
+//!     D: ::serde::Deserializer<'de>;
+//! ```
+
+/// Serialize/deserialize a byte sequence as a lowercase hex string.
+pub mod hex {
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::Serializer;
+
+    pub fn serialize<S>(val: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = String::with_capacity(val.len() * 2);
+        for byte in val {
+            s.push_str(&format!("{:02x}", byte));
+        }
+        serializer.serialize_str(&s)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        // Work on bytes, not `str` slicing: the input is untrusted wire data
+        // and a multi-byte UTF-8 character whose boundary falls on an odd
+        // offset would panic on `&s[i..i + 2]` instead of erroring.
+        let bytes = s.as_bytes();
+        if bytes.len() % 2 != 0 {
+            return Err(::serde::de::Error::custom(
+                "hex string must have an even number of characters",
+            ));
+        }
+        bytes
+            .chunks(2)
+            .map(|pair| {
+                let hi = (pair[0] as char)
+                    .to_digit(16)
+                    .ok_or_else(|| ::serde::de::Error::custom("invalid hex digit"))?;
+                let lo = (pair[1] as char)
+                    .to_digit(16)
+                    .ok_or_else(|| ::serde::de::Error::custom("invalid hex digit"))?;
+                Ok((hi * 16 + lo) as u8)
+            })
+            .collect()
+    }
+}
+
+/// Serialize/deserialize a byte sequence as a base64 string.
+pub mod base64 {
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::Serializer;
+
+    pub fn serialize<S>(val: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&::base64::encode(val))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        ::base64::decode(&s).map_err(|e| ::serde::de::Error::custom(format!("invalid base64: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn roundtrip_hex(bytes: &[u8]) -> Vec<u8> {
+        let encoded = serde_json::to_string(&Wrapper::Hex(bytes.to_vec())).unwrap();
+        match serde_json::from_str(&encoded).unwrap() {
+            Wrapper::Hex(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    fn roundtrip_base64(bytes: &[u8]) -> Vec<u8> {
+        let encoded = serde_json::to_string(&Wrapper::Base64(bytes.to_vec())).unwrap();
+        match serde_json::from_str(&encoded).unwrap() {
+            Wrapper::Base64(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    enum Wrapper {
+        Hex(#[serde(with = "hex")] Vec<u8>),
+        Base64(#[serde(with = "base64")] Vec<u8>),
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        assert_eq!(roundtrip_hex(&[]), Vec::<u8>::new());
+        assert_eq!(roundtrip_hex(&[0x00, 0xab, 0xff]), vec![0x00, 0xab, 0xff]);
+    }
+
+    #[test]
+    fn hex_rejects_odd_length() {
+        assert!(hex::deserialize(&mut serde_json::Deserializer::from_str("\"abc\"")).is_err());
+    }
+
+    #[test]
+    fn hex_rejects_multibyte_input_without_panicking() {
+        // Regression test: byte-slicing the input string used to panic with
+        // "byte index is not a char boundary" on multi-byte UTF-8 input of
+        // even byte length instead of returning an error.
+        let result = hex::deserialize(&mut serde_json::Deserializer::from_str("\"aée\""));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn base64_roundtrip() {
+        assert_eq!(roundtrip_base64(&[]), Vec::<u8>::new());
+        assert_eq!(
+            roundtrip_base64(&[0x00, 0xab, 0xff, 0x10]),
+            vec![0x00, 0xab, 0xff, 0x10]
+        );
+    }
+}